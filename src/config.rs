@@ -1,4 +1,13 @@
-#[derive(Debug, Clone)]
+use std::io::Write;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::GptError;
+use crate::providers::ProviderConfig;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct GptConfig {
     pub temperature: f32,
     pub max_tokens: u32,
@@ -6,6 +15,10 @@ pub struct GptConfig {
     pub frequency_penalty: f32,
     pub presence_penalty: f32,
     pub stop: Option<Vec<String>>,
+    /// The model's total context window in tokens, if known. When set,
+    /// `GptClient::ask_in` trims the oldest non-system messages so the
+    /// conversation plus `max_tokens` fits before sending a request.
+    pub context_window: Option<u32>,
 }
 
 impl Default for GptConfig {
@@ -17,6 +30,7 @@ impl Default for GptConfig {
             frequency_penalty: 0.0,
             presence_penalty: 0.0,
             stop: None,
+            context_window: None,
         }
     }
 }
@@ -35,6 +49,7 @@ pub struct GptConfigBuilder {
     frequency_penalty: Option<f32>,
     presence_penalty: Option<f32>,
     stop: Option<Vec<String>>,
+    context_window: Option<u32>,
 }
 
 impl GptConfigBuilder {
@@ -74,6 +89,12 @@ impl GptConfigBuilder {
         self
     }
 
+    pub fn context_window(mut self, context_window: u32) -> Self {
+        tracing::debug!("Setting context_window to: {}", context_window);
+        self.context_window = Some(context_window);
+        self
+    }
+
     pub fn build(self) -> GptConfig {
         let default = GptConfig::default();
         tracing::info!("Building GPT configuration");
@@ -84,6 +105,97 @@ impl GptConfigBuilder {
             frequency_penalty: self.frequency_penalty.unwrap_or(default.frequency_penalty),
             presence_penalty: self.presence_penalty.unwrap_or(default.presence_penalty),
             stop: self.stop,
+            context_window: self.context_window,
         }
     }
 }
+
+/// The top-level shape of a YAML config file: generation defaults plus the
+/// list of providers it configures.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppConfig {
+    #[serde(flatten)]
+    pub generation: GptConfig,
+    #[serde(default)]
+    pub clients: Vec<ProviderConfig>,
+}
+
+/// Loads an [`AppConfig`] from a YAML file.
+pub fn load_from_file(path: impl AsRef<Path>) -> Result<AppConfig, GptError> {
+    let path = path.as_ref();
+    let contents = std::fs::read_to_string(path).map_err(|e| {
+        GptError::ConfigError(format!("Failed to read config file {}: {}", path.display(), e))
+    })?;
+
+    serde_yaml::from_str(&contents)
+        .map_err(|e| GptError::ConfigError(format!("Failed to parse config file {}: {}", path.display(), e)))
+}
+
+/// Loads the config file at `path`, or runs an interactive first-run setup
+/// (prompting for provider, API key and URL) and writes the file if it
+/// doesn't exist yet.
+pub fn load_or_init(path: impl AsRef<Path>) -> Result<AppConfig, GptError> {
+    let path = path.as_ref();
+
+    if path.exists() {
+        return load_from_file(path);
+    }
+
+    println!("No configuration file found at {}.", path.display());
+    println!("Let's set up your first provider.\n");
+
+    let provider_type = prompt("Provider type (azure/openai) [azure]: ")?;
+    let api_url = prompt("API URL: ")?;
+    let api_key = prompt("API key: ")?;
+
+    let client = match provider_type.trim() {
+        "openai" => {
+            let model = prompt("Model [gpt-4o]: ")?;
+            let model = if model.is_empty() { "gpt-4o".to_string() } else { model };
+
+            ProviderConfig::OpenAi(crate::providers::openai::OpenAiConfig {
+                name: Some("default".to_string()),
+                api_url: if api_url.is_empty() {
+                    "https://api.openai.com/v1/chat/completions".to_string()
+                } else {
+                    api_url
+                },
+                api_key,
+                model,
+                organization_id: None,
+                extra: None,
+            })
+        }
+        _ => ProviderConfig::Azure(crate::providers::azure::AzureConfig {
+            name: Some("default".to_string()),
+            api_url,
+            api_key,
+            extra: None,
+        }),
+    };
+
+    let config = AppConfig {
+        generation: GptConfig::default(),
+        clients: vec![client],
+    };
+
+    let yaml = serde_yaml::to_string(&config)
+        .map_err(|e| GptError::ConfigError(format!("Failed to serialize config: {}", e)))?;
+    std::fs::write(path, yaml)
+        .map_err(|e| GptError::ConfigError(format!("Failed to write config file {}: {}", path.display(), e)))?;
+
+    println!("\nWrote configuration to {}", path.display());
+    Ok(config)
+}
+
+fn prompt(label: &str) -> Result<String, GptError> {
+    print!("{}", label);
+    std::io::stdout().flush().ok();
+
+    let mut input = String::new();
+    std::io::stdin()
+        .read_line(&mut input)
+        .map_err(|e| GptError::ConfigError(format!("Failed to read input: {}", e)))?;
+
+    Ok(input.trim().to_string())
+}
@@ -0,0 +1,171 @@
+// conversation.rs
+
+use crate::error::GptError;
+use crate::models::{Message, Role, ToolCall};
+use crate::tokenizer::count_message_tokens;
+
+/// Tracks the turns of an ongoing chat so a `GptClient` can send the full
+/// history on every request instead of a single stateless message.
+///
+/// A conversation optionally starts with a system prompt, which [`reset`]
+/// preserves so restarting a chat doesn't also forget its instructions.
+///
+/// [`reset`]: Conversation::reset
+#[derive(Debug, Clone, Default)]
+pub struct Conversation {
+    system_prompt: Option<String>,
+    messages: Vec<Message>,
+}
+
+impl Conversation {
+    /// Creates an empty conversation with no system prompt.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a conversation that opens with the given system prompt.
+    pub fn with_system_prompt(system_prompt: impl Into<String>) -> Self {
+        let system_prompt = system_prompt.into();
+        Self {
+            messages: vec![Message::system(system_prompt.clone())],
+            system_prompt: Some(system_prompt),
+        }
+    }
+
+    /// Appends a user turn.
+    pub fn push_user(&mut self, content: impl Into<String>) {
+        self.messages.push(Message::user(content));
+    }
+
+    /// Appends an assistant turn.
+    pub fn push_assistant(&mut self, content: impl Into<String>) {
+        self.messages.push(Message::assistant(content));
+    }
+
+    /// Appends an assistant turn that requests tool calls instead of answering.
+    pub fn push_assistant_tool_calls(&mut self, tool_calls: Vec<ToolCall>) {
+        self.messages.push(Message::assistant_tool_calls(tool_calls));
+    }
+
+    /// Appends a tool's result in reply to one of its tool calls.
+    pub fn push_tool_result(&mut self, tool_call_id: impl Into<String>, content: impl Into<String>) {
+        self.messages.push(Message::tool(tool_call_id, content));
+    }
+
+    /// The full message history, in order, as sent to the model.
+    pub fn messages(&self) -> &[Message] {
+        &self.messages
+    }
+
+    /// Clears the conversation back to just its system prompt, if any.
+    pub fn reset(&mut self) {
+        self.messages.clear();
+        if let Some(system_prompt) = &self.system_prompt {
+            self.messages.push(Message::system(system_prompt.clone()));
+        }
+    }
+
+    /// Drops the oldest non-system messages until the estimated token count
+    /// of the history fits within `budget`.
+    ///
+    /// An `assistant` turn that requested tool calls is dropped together with
+    /// the `tool` messages answering it, never split from them, since a
+    /// provider rejects a history where a `tool` message's preceding
+    /// `tool_calls` turn is missing.
+    ///
+    /// Returns [`GptError::ContextOverflow`] if even the system prompt plus
+    /// the single most recent turn (or group) wouldn't fit.
+    pub fn trim_to_budget(&mut self, budget: usize) -> Result<(), GptError> {
+        let start = if self.system_prompt.is_some() { 1 } else { 0 };
+
+        while count_message_tokens(&self.messages) > budget {
+            if self.messages.len() <= start {
+                return Err(GptError::ContextOverflow(
+                    "the most recent turn alone exceeds the configured context window".to_string(),
+                ));
+            }
+
+            let group_len = self.removable_group_len(start);
+            if self.messages.len() - start <= group_len {
+                return Err(GptError::ContextOverflow(
+                    "the most recent turn alone exceeds the configured context window".to_string(),
+                ));
+            }
+
+            self.messages.drain(start..start + group_len);
+        }
+
+        Ok(())
+    }
+
+    /// The number of messages starting at `start` that must be removed as a
+    /// unit: an `assistant` turn carrying `tool_calls` plus every `tool`
+    /// message immediately following it that answers those calls.
+    fn removable_group_len(&self, start: usize) -> usize {
+        let mut len = 1;
+        if self.messages[start].tool_calls.is_some() {
+            while self.messages.get(start + len).map(|message| message.role) == Some(Role::Tool) {
+                len += 1;
+            }
+        }
+        len
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ToolCallFunction;
+
+    fn tool_call(id: &str) -> ToolCall {
+        ToolCall {
+            id: Some(id.to_string()),
+            function: ToolCallFunction { name: Some("lookup".to_string()), arguments: "{}".to_string() },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn trim_to_budget_drops_oldest_turn_first() {
+        let mut conversation = Conversation::with_system_prompt("system");
+        conversation.push_user("first");
+        conversation.push_assistant("first reply");
+        conversation.push_user("second");
+        conversation.push_assistant("second reply");
+
+        let budget = count_message_tokens(conversation.messages()) - 1;
+        conversation.trim_to_budget(budget).unwrap();
+
+        let remaining: Vec<_> = conversation.messages().iter().map(|m| m.content.as_str()).collect();
+        assert_eq!(remaining, vec!["system", "first reply", "second", "second reply"]);
+    }
+
+    #[test]
+    fn trim_to_budget_drops_tool_call_group_as_a_unit() {
+        let mut conversation = Conversation::new();
+        conversation.push_user("first");
+        conversation.push_assistant_tool_calls(vec![tool_call("call-1"), tool_call("call-2")]);
+        conversation.push_tool_result("call-1", "result-1");
+        conversation.push_tool_result("call-2", "result-2");
+        conversation.push_user("second");
+        conversation.push_assistant("second reply");
+
+        // Only the last exchange fits, so the tool-call group has to go too.
+        let budget = count_message_tokens(&conversation.messages()[4..]);
+        conversation.trim_to_budget(budget).unwrap();
+
+        let remaining = conversation.messages();
+        assert!(remaining.iter().all(|m| m.role != Role::Tool));
+        assert_eq!(remaining[0].content, "second");
+    }
+
+    #[test]
+    fn trim_to_budget_errors_when_last_turn_alone_overflows() {
+        let mut conversation = Conversation::with_system_prompt("system");
+        conversation.push_user("the only turn that's left");
+
+        let result = conversation.trim_to_budget(1);
+
+        assert!(matches!(result, Err(GptError::ContextOverflow(_))));
+    }
+}
@@ -19,4 +19,19 @@ pub enum GptError {
 
     #[error("Configuration error: {0}")]
     ConfigError(String),
+
+    #[error("Unknown tool requested by the model: {0}")]
+    UnknownTool(String),
+
+    #[error("Tool '{0}' failed: {1}")]
+    ToolError(String, String),
+
+    #[error("Exceeded the maximum of {0} tool-calling steps")]
+    ToolLoopExceeded(usize),
+
+    #[error("Request timed out")]
+    Timeout,
+
+    #[error("Conversation does not fit in the context window: {0}")]
+    ContextOverflow(String),
 }
\ No newline at end of file
@@ -1,30 +1,49 @@
-use reqwest::{Client, header};
-use futures::StreamExt;
-use tokio::sync::mpsc;
+use std::sync::Arc;
+use std::time::Duration;
+
+use reqwest::Client;
+use tokio::sync::{mpsc, Mutex};
 use tokio_stream::wrappers::ReceiverStream;
 
 // Declare our modules
 pub mod error;
 pub mod config;
+pub mod conversation;
 pub mod models;
+pub mod providers;
+pub mod retry;
+pub mod stream;
+pub mod tokenizer;
+pub mod tools;
 
 // Import types from our modules
 use crate::error::GptError;
 use crate::config::GptConfig;
+use crate::conversation::Conversation;
+use crate::providers::GptProvider;
+use crate::retry::RetryConfig;
+use crate::stream::{stream_round, StreamEvent};
+use crate::tools::ToolRegistry;
 
 use crate::models::{
     GptRequest,
     Message,
     GptResponse,
+    StreamOptions,
 };
 
+/// Upper bound on the tool-calling loop in [`GptClient::ask_in`] and the
+/// streaming loop behind [`GptClient::ask_stream`]/[`GptClient::ask_stream_in`],
+/// so a model that keeps requesting tool calls can't run forever.
+const MAX_TOOL_STEPS: usize = 8;
 
 // Define our main client structure
 pub struct GptClient {
     client: Client,
-    api_url: String,
-    api_key: String,
+    provider: Arc<dyn GptProvider>,
     config: GptConfig,
+    tools: Arc<ToolRegistry>,
+    retry: RetryConfig,
 }
 
 // Implement the core functionality
@@ -34,190 +53,280 @@ impl GptClient {
         GptClientBuilder::default()
     }
 
-    // Helper method to build headers
-    fn build_headers(&self) -> Result<header::HeaderMap, GptError> {
-        let mut headers = header::HeaderMap::new();
-
-        // Add the API key header
-        headers.insert(
-            "api-key",
-            header::HeaderValue::from_str(&self.api_key)?
-        );
-
-        // Add content type for JSON
-        headers.insert(
-            header::CONTENT_TYPE,
-            header::HeaderValue::from_static("application/json"),
-        );
-
-        Ok(headers)
-    }
-
-    // Helper method to build the request body
-    fn build_request(&self, message: &str) -> GptRequest {
-        GptRequest {
-            messages: vec![Message {
-                role: "user".to_string(),
-                content: message.to_string(),
-            }],
-            temperature: self.config.temperature,
-            max_tokens: self.config.max_tokens,
-            top_p: self.config.top_p,
-            frequency_penalty: self.config.frequency_penalty,
-            presence_penalty: self.config.presence_penalty,
-            stop: self.config.stop.clone(),
-            stream: false,
-        }
+    // Helper method to build the request body from a full message history
+    fn build_request(&self, messages: Vec<Message>) -> GptRequest {
+        build_request(&self.config, &self.tools, messages)
     }
 
-    // Method for regular (non-streaming) requests
+    // Method for regular (non-streaming), stateless requests
     pub async fn ask(&self, message: &str) -> Result<String, GptError> {
         tracing::info!("Sending request to GPT API");
         tracing::debug!("Message content length: {}", message.len());
 
-        let headers = self.build_headers()?;
-        let mut request = self.build_request(message);
+        let mut request = self.build_request(vec![Message::user(message)]);
         request.stream = false;
 
-        let response = self.client
-            .post(&self.api_url)
-            .headers(headers)
-            .json(&request)
-            .send()
-            .await?;
+        let response_data = self.retry.run(|| self.provider.complete(&self.client, &request)).await?;
 
-        let status = response.status();
-        tracing::debug!("Received response with status: {}", status);
+        tracing::info!("Successfully received and parsed API response");
+        extract_content(&response_data)
+    }
 
-        if !status.is_success() {
-            return self.handle_error_response(response, status).await;
-        }
+    // Method for regular (non-streaming) requests against a running conversation.
+    // Runs a tool-calling loop: whenever the model asks for tool calls instead of
+    // answering, the matching registered tools are invoked and their results fed
+    // back, up to `MAX_TOOL_STEPS` rounds.
+    pub async fn ask_in(&self, conversation: &mut Conversation, message: &str) -> Result<String, GptError> {
+        tracing::info!("Sending conversational request to GPT API");
 
-        let response_data: GptResponse = response.json().await
-            .map_err(|e| {
-                tracing::error!("Failed to parse API response: {}", e);
-                GptError::ParseError(e.to_string())
-            })?;
+        conversation.push_user(message);
 
-        tracing::info!("Successfully received and parsed API response");
-        response_data.choices.first()
-            .and_then(|choice| choice.message.as_ref().map(|msg| msg.content.clone()))
-            .ok_or_else(|| {
-                tracing::error!("No response content available in API response");
-                GptError::ParseError("No response content available".to_string())
-            })
+        for step in 0..MAX_TOOL_STEPS {
+            if let Some(context_window) = self.config.context_window {
+                let budget = (context_window as usize).saturating_sub(self.config.max_tokens as usize);
+                conversation.trim_to_budget(budget)?;
+            }
+
+            let mut request = self.build_request(conversation.messages().to_vec());
+            request.stream = false;
+
+            let response = self.retry.run(|| self.provider.complete(&self.client, &request)).await?;
+            let choice = response.choices.first()
+                .ok_or_else(|| GptError::ParseError("No choices in API response".to_string()))?;
+
+            if choice.finish_reason.as_deref() != Some("tool_calls") {
+                let content = extract_content(&response)?;
+                conversation.push_assistant(content.clone());
+                return Ok(content);
+            }
+
+            tracing::info!("Model requested tool calls (step {})", step + 1);
+            let response_message = choice.message.as_ref()
+                .ok_or_else(|| GptError::ParseError("Missing message for tool_calls response".to_string()))?;
+            let tool_calls = response_message.tool_calls.clone().unwrap_or_default();
+
+            conversation.push_assistant_tool_calls(tool_calls.clone());
+
+            for tool_call in &tool_calls {
+                let name = tool_call.function.name.clone().unwrap_or_default();
+                let tool = self.tools.get(&name)
+                    .ok_or_else(|| GptError::UnknownTool(name.clone()))?;
+
+                let args = serde_json::from_str(&tool_call.function.arguments)
+                    .map_err(|e| GptError::ToolError(name.clone(), format!("malformed arguments: {}", e)))?;
+                let result = tool.call(args).await
+                    .map_err(|e| GptError::ToolError(name.clone(), e.to_string()))?;
+
+                conversation.push_tool_result(tool_call.id.clone().unwrap_or_default(), result);
+            }
+        }
+
+        Err(GptError::ToolLoopExceeded(MAX_TOOL_STEPS))
     }
 
-    // Method for streaming requests
-    pub async fn ask_stream(&self, message: &str) -> Result<ReceiverStream<Result<String, GptError>>, GptError> {
+    // Method for streaming, stateless requests. Runs the same tool-calling
+    // loop as `ask_in`, just streamed: each round's content/finish-reason/usage
+    // are forwarded to the returned stream as they arrive, and a round ending
+    // in `finish_reason: "tool_calls"` invokes the matching tools and starts
+    // another round instead of ending the stream, up to `MAX_TOOL_STEPS`.
+    pub async fn ask_stream(&self, message: &str) -> Result<ReceiverStream<Result<StreamEvent, GptError>>, GptError> {
         tracing::info!("Starting streaming request to GPT API");
 
-        let mut headers = self.build_headers()?;
-        headers.insert(
-            "Accept",
-            header::HeaderValue::from_static("text/event-stream"),
-        );
+        let (tx, rx) = mpsc::channel(100);
+        tokio::spawn(run_tool_streaming_loop(
+            self.client.clone(),
+            Arc::clone(&self.provider),
+            Arc::clone(&self.tools),
+            self.retry.clone(),
+            self.config.clone(),
+            vec![Message::user(message)],
+            None,
+            tx,
+        ));
 
-        let mut request = self.build_request(message);
-        request.stream = true;
+        Ok(ReceiverStream::new(rx))
+    }
 
-        let response = self.client
-            .post(&self.api_url)
-            .headers(headers)
-            .json(&request)
-            .send()
-            .await?;
+    // Method for streaming requests against a running conversation. The user
+    // turn is appended before the request goes out; `conversation` is shared
+    // behind a mutex with the background loop driving the stream, which
+    // appends each round's assistant/tool-call/tool-result turns to it as the
+    // tool-calling loop progresses, the same way `ask_in` does synchronously.
+    pub async fn ask_stream_in(
+        &self,
+        conversation: Arc<Mutex<Conversation>>,
+        message: &str,
+    ) -> Result<ReceiverStream<Result<StreamEvent, GptError>>, GptError> {
+        tracing::info!("Starting streaming conversational request to GPT API");
 
-        let status = response.status();
-        if !status.is_success() {
-            return self.handle_error_response(response, status).await;
-        }
+        conversation.lock().await.push_user(message);
 
         let (tx, rx) = mpsc::channel(100);
-        let mut stream = response.bytes_stream();
-
-        tokio::spawn(async move {
-            let mut buffer = String::new();
-
-            while let Some(chunk_result) = stream.next().await {
-                match chunk_result {
-                    Ok(chunk) => {
-                        if let Ok(text) = String::from_utf8(chunk.to_vec()) {
-                            buffer.push_str(&text);
-
-                            while let Some(pos) = buffer.find("\n\n") {
-                                let message = buffer[..pos].to_string();
-                                buffer = buffer[pos + 2..].to_string();
-
-                                if message.starts_with("data: ") {
-                                    let data = message.trim_start_matches("data: ");
-                                    if data == "[DONE]" {
-                                        break;
-                                    }
-
-                                    match serde_json::from_str::<GptResponse>(data) {
-                                        Ok(response) => {
-                                            if let Some(choice) = response.choices.first() {
-                                                if let Some(delta) = &choice.delta {
-                                                    if let Some(content) = &delta.content {
-                                                        if !content.is_empty() {
-                                                            let _ = tx.send(Ok(content.clone())).await;
-                                                        }
-                                                    }
-                                                }
-                                            }
-                                        }
-                                        Err(e) => {
-                                            tracing::error!("Failed to parse stream data: {}", e);
-                                            let _ = tx.send(Err(GptError::ParseError(e.to_string()))).await;
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    }
-                    Err(e) => {
-                        let _ = tx.send(Err(GptError::RequestError(e))).await;
-                        break;
-                    }
-                }
-            }
-        });
+        tokio::spawn(run_tool_streaming_loop(
+            self.client.clone(),
+            Arc::clone(&self.provider),
+            Arc::clone(&self.tools),
+            self.retry.clone(),
+            self.config.clone(),
+            Vec::new(),
+            Some(conversation),
+            tx,
+        ));
 
         Ok(ReceiverStream::new(rx))
     }
+}
 
-    // Helper method to handle error responses
-    async fn handle_error_response<T>(&self, response: reqwest::Response, status: reqwest::StatusCode) -> Result<T, GptError> {
-        let error_message = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
-        tracing::error!("API request failed: {} - {}", status, error_message);
-        Err(GptError::ApiError {
-            status_code: status.as_u16(),
-            message: error_message,
+fn extract_content(response: &GptResponse) -> Result<String, GptError> {
+    response.choices.first()
+        .and_then(|choice| choice.message.as_ref())
+        .and_then(|msg| msg.content.clone())
+        .ok_or_else(|| {
+            tracing::error!("No response content available in API response");
+            GptError::ParseError("No response content available".to_string())
         })
+}
+
+// Builds the request body from a full message history; shared by the
+// synchronous methods (via `GptClient::build_request`) and the streaming tool
+// loop, which can't borrow `&GptClient` across a spawned, 'static task.
+fn build_request(config: &GptConfig, tools: &ToolRegistry, messages: Vec<Message>) -> GptRequest {
+    GptRequest {
+        model: None,
+        messages,
+        temperature: config.temperature,
+        max_tokens: config.max_tokens,
+        top_p: config.top_p,
+        frequency_penalty: config.frequency_penalty,
+        presence_penalty: config.presence_penalty,
+        stop: config.stop.clone(),
+        tools: if tools.is_empty() { None } else { Some(tools.schemas()) },
+        tool_choice: None,
+        stream: false,
+        stream_options: None,
     }
 }
 
+// Drives the streaming counterpart of `ask_in`'s tool-calling loop in a
+// spawned task: each round streams a response, merging any tool-call argument
+// fragments by `index` (see `stream::stream_round`); a round that ends in
+// `finish_reason: "tool_calls"` runs the matching tools and appends their
+// results before looping, the same way `ask_in` does for non-streaming
+// requests. `conversation` is `None` for the stateless `ask_stream`, in which
+// case the round-to-round history is threaded through `local_messages` instead.
+async fn run_tool_streaming_loop(
+    client: Client,
+    provider: Arc<dyn GptProvider>,
+    tools: Arc<ToolRegistry>,
+    retry: RetryConfig,
+    config: GptConfig,
+    mut local_messages: Vec<Message>,
+    conversation: Option<Arc<Mutex<Conversation>>>,
+    tx: mpsc::Sender<Result<StreamEvent, GptError>>,
+) {
+    for step in 0..MAX_TOOL_STEPS {
+        let messages = match &conversation {
+            Some(conversation) => {
+                let mut conv = conversation.lock().await;
+
+                if let Some(context_window) = config.context_window {
+                    let budget = (context_window as usize).saturating_sub(config.max_tokens as usize);
+                    if let Err(e) = conv.trim_to_budget(budget) {
+                        let _ = tx.send(Err(e)).await;
+                        return;
+                    }
+                }
+
+                conv.messages().to_vec()
+            }
+            None => local_messages.clone(),
+        };
+
+        let mut request = build_request(&config, &tools, messages);
+        request.stream = true;
+        request.stream_options = Some(StreamOptions { include_usage: true });
+
+        let response = match retry.run(|| provider.complete_stream(&client, &request)).await {
+            Ok(response) => response,
+            Err(e) => {
+                let _ = tx.send(Err(e)).await;
+                return;
+            }
+        };
+
+        let outcome = stream_round(response, &tx).await;
+
+        if outcome.finish_reason.as_deref() != Some("tool_calls") {
+            if !outcome.content.is_empty() {
+                match &conversation {
+                    Some(conversation) => conversation.lock().await.push_assistant(outcome.content),
+                    None => local_messages.push(Message::assistant(outcome.content)),
+                }
+            }
+            return;
+        }
+
+        tracing::info!("Model requested tool calls during streaming (step {})", step + 1);
+
+        match &conversation {
+            Some(conversation) => conversation.lock().await.push_assistant_tool_calls(outcome.tool_calls.clone()),
+            None => local_messages.push(Message::assistant_tool_calls(outcome.tool_calls.clone())),
+        }
+
+        for tool_call in &outcome.tool_calls {
+            let name = tool_call.function.name.clone().unwrap_or_default();
+
+            let tool = match tools.get(&name) {
+                Some(tool) => tool,
+                None => {
+                    let _ = tx.send(Err(GptError::UnknownTool(name))).await;
+                    return;
+                }
+            };
+
+            let args = match serde_json::from_str(&tool_call.function.arguments) {
+                Ok(args) => args,
+                Err(e) => {
+                    let _ = tx.send(Err(GptError::ToolError(name, format!("malformed arguments: {}", e)))).await;
+                    return;
+                }
+            };
+
+            let result = match tool.call(args).await {
+                Ok(result) => result,
+                Err(e) => {
+                    let _ = tx.send(Err(GptError::ToolError(name, e.to_string()))).await;
+                    return;
+                }
+            };
+
+            let tool_call_id = tool_call.id.clone().unwrap_or_default();
+            match &conversation {
+                Some(conversation) => conversation.lock().await.push_tool_result(tool_call_id, result),
+                None => local_messages.push(Message::tool(tool_call_id, result)),
+            }
+        }
+    }
+
+    let _ = tx.send(Err(GptError::ToolLoopExceeded(MAX_TOOL_STEPS))).await;
+}
+
 // Builder implementation for creating client instances
 #[derive(Default)]
 pub struct GptClientBuilder {
-    api_url: Option<String>,
-    api_key: Option<String>,
+    provider: Option<Box<dyn GptProvider>>,
     config: Option<GptConfig>,
+    tools: ToolRegistry,
+    connect_timeout: Option<Duration>,
+    timeout: Option<Duration>,
+    proxy: Option<String>,
+    retry: RetryConfig,
 }
 
 impl GptClientBuilder {
-    pub fn api_url(mut self, url: impl Into<String>) -> Self {
-        let url = url.into();
-        tracing::debug!("Setting API URL: {}", url);
-        self.api_url = Some(url);
-        self
-    }
-
-    pub fn api_key(mut self, key: impl Into<String>) -> Self {
-        let key = key.into();
-        tracing::debug!("Setting API key: {}", "*".repeat(key.len()));
-        self.api_key = Some(key);
+    pub fn provider(mut self, provider: Box<dyn GptProvider>) -> Self {
+        tracing::debug!("Setting provider");
+        self.provider = Some(provider);
         self
     }
 
@@ -227,26 +336,81 @@ impl GptClientBuilder {
         self
     }
 
+    // Registers a tool the model may call during `ask_in`.
+    pub fn tool(mut self, tool: Box<dyn crate::tools::Tool>) -> Self {
+        tracing::debug!("Registering tool: {}", tool.name());
+        self.tools.register(tool);
+        self
+    }
+
+    /// Caps how long connection establishment is allowed to take.
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Caps how long a whole request (connect + send + receive) is allowed to take.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Routes requests through an HTTP(S) or SOCKS5 proxy. If not set, falls
+    /// back to the `HTTPS_PROXY`/`ALL_PROXY` environment variables at build time.
+    pub fn proxy(mut self, proxy_url: impl Into<String>) -> Self {
+        self.proxy = Some(proxy_url.into());
+        self
+    }
+
+    /// Sets how many times a transient failure (connection errors, timeouts,
+    /// HTTP 429/5xx) is retried, with exponential backoff between attempts.
+    pub fn max_retries(mut self, max_retries: u32) -> Self {
+        self.retry.max_retries = max_retries;
+        self
+    }
+
     pub fn build(self) -> Result<GptClient, GptError> {
         tracing::info!("Building GPT client");
 
-        let api_url = self.api_url
+        let provider = self.provider
             .ok_or_else(|| {
-                tracing::error!("API URL is required but not provided");
-                GptError::ConfigError("API URL is required".to_string())
+                tracing::error!("A provider is required but not provided");
+                GptError::ConfigError("A provider is required".to_string())
             })?;
 
-        let api_key = self.api_key
-            .ok_or_else(|| {
-                tracing::error!("API key is required but not provided");
-                GptError::ConfigError("API key is required".to_string())
-            })?;
+        let mut http_builder = Client::builder();
+
+        if let Some(timeout) = self.timeout {
+            http_builder = http_builder.timeout(timeout);
+        }
+
+        if let Some(connect_timeout) = self.connect_timeout {
+            http_builder = http_builder.connect_timeout(connect_timeout);
+        }
+
+        let proxy_url = self.proxy
+            .or_else(|| std::env::var("HTTPS_PROXY").ok())
+            .or_else(|| std::env::var("ALL_PROXY").ok());
+
+        if let Some(proxy_url) = proxy_url {
+            let proxy = if proxy_url.starts_with("socks5://") {
+                reqwest::Proxy::all(&proxy_url)
+            } else {
+                reqwest::Proxy::https(&proxy_url)
+            }.map_err(|e| GptError::ConfigError(format!("Invalid proxy URL '{}': {}", proxy_url, e)))?;
+
+            http_builder = http_builder.proxy(proxy);
+        }
+
+        let client = http_builder.build()
+            .map_err(|e| GptError::ConfigError(format!("Failed to build HTTP client: {}", e)))?;
 
         Ok(GptClient {
-            client: Client::new(),
-            api_url,
-            api_key,
+            client,
+            provider: Arc::from(provider),
             config: self.config.unwrap_or_default(),
+            tools: Arc::new(self.tools),
+            retry: self.retry,
         })
     }
-}
\ No newline at end of file
+}
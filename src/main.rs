@@ -1,8 +1,10 @@
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use std::env;
 use std::io::{self, Write};
-use explore::{GptClient, config::GptConfig};
+use std::sync::Arc;
+use explore::{config, conversation::Conversation, stream::StreamEvent, GptClient};
 use futures::StreamExt;
+use tokio::sync::Mutex;
 use tracing_subscriber::EnvFilter;
 
 async fn get_user_input(prompt: &str) -> Result<String> {
@@ -29,22 +31,37 @@ async fn main() -> Result<()> {
 
     dotenv::dotenv().ok();
 
-    let config = GptConfig::builder()
-        .temperature(0.8)
-        .max_tokens(1000)
-        .build();
+    let config_path = env::var("EXPLORE_CONFIG_PATH").unwrap_or_else(|_| "explore.yaml".to_string());
+    let app_config = config::load_or_init(&config_path)?;
 
-    let client = GptClient::builder()
-        .api_url(env::var("AZUREOPENAI_API_URL")?)
-        .api_key(env::var("AZUREOPENAI_API_KEY")?)
-        .config(config)
-        .build()?;
+    let provider_config = app_config.clients.into_iter().next()
+        .ok_or_else(|| anyhow!("Config file {} has no clients configured", config_path))?;
+
+    let mut builder = GptClient::builder().config(app_config.generation);
+
+    if let Some(extra) = provider_config.extra() {
+        if let Some(proxy) = &extra.proxy {
+            builder = builder.proxy(proxy.clone());
+        }
+        if let Some(timeout_secs) = extra.timeout_secs {
+            builder = builder.timeout(std::time::Duration::from_secs(timeout_secs));
+        }
+        if let Some(connect_timeout_secs) = extra.connect_timeout_secs {
+            builder = builder.connect_timeout(std::time::Duration::from_secs(connect_timeout_secs));
+        }
+    }
+
+    let client = builder.provider(provider_config.build()).build()?;
 
     println!("Welcome to Enhanced GPT Client!");
     println!("Type 'exit' to quit the program.");
-    println!("Use '/stream' to toggle streaming mode (currently: OFF)\n");
+    println!("Use '/stream' to toggle streaming mode (currently: OFF)");
+    println!("Use '/reset' to clear the conversation history\n");
 
     let mut streaming_mode = false;
+    let conversation = Arc::new(Mutex::new(Conversation::with_system_prompt(
+        "You are a helpful assistant.",
+    )));
 
     loop {
         let input = get_user_input("You: ").await?;
@@ -60,19 +77,35 @@ async fn main() -> Result<()> {
                 tracing::info!("Streaming mode toggled to: {}", streaming_mode);
                 println!("Streaming mode: {}", if streaming_mode { "ON" } else { "OFF" });
             }
+            "/reset" => {
+                conversation.lock().await.reset();
+                tracing::info!("Conversation history reset");
+                println!("Conversation history cleared.");
+            }
             _ => {
                 if streaming_mode {
-                    match client.ask_stream(&input).await {
+                    match client.ask_stream_in(conversation.clone(), &input).await {
                         Ok(mut stream) => {
                             print!("GPT: ");
                             io::stdout().flush()?;
 
                             while let Some(result) = stream.next().await {
                                 match result {
-                                    Ok(content) => {
+                                    Ok(StreamEvent::Content(content)) => {
                                         print!("{}", content);
                                         io::stdout().flush()?;
                                     }
+                                    Ok(StreamEvent::FinishReason(reason)) => {
+                                        tracing::debug!("Stream finished: {}", reason);
+                                    }
+                                    Ok(StreamEvent::Usage(usage)) => {
+                                        tracing::debug!(
+                                            "Token usage - prompt: {}, completion: {}, total: {}",
+                                            usage.prompt_tokens,
+                                            usage.completion_tokens,
+                                            usage.total_tokens
+                                        );
+                                    }
                                     Err(e) => {
                                         eprintln!("\nError in stream: {}", e);
                                         break;
@@ -87,7 +120,8 @@ async fn main() -> Result<()> {
                         }
                     }
                 } else {
-                    match client.ask(&input).await {
+                    let mut conversation = conversation.lock().await;
+                    match client.ask_in(&mut conversation, &input).await {
                         Ok(response) => {
                             println!("\nGPT: {}\n", response);
                         }
@@ -1,20 +1,116 @@
 // models.rs
 
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Who a message in a conversation is attributed to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    System,
+    User,
+    Assistant,
+    Tool,
+}
 
 // Request-related models
 /// Represents a message in the conversation with the GPT model.
 /// Each message has a role (like "user" or "assistant") and content.
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 pub struct Message {
-    pub role: String,
+    pub role: Role,
     pub content: String,
+    /// Set on `tool` messages to say which tool call this is a result for.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_call_id: Option<String>,
+    /// Set on `assistant` messages that requested one or more tool calls.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_calls: Option<Vec<ToolCall>>,
+}
+
+impl Message {
+    pub fn system(content: impl Into<String>) -> Self {
+        Self { role: Role::System, content: content.into(), tool_call_id: None, tool_calls: None }
+    }
+
+    pub fn user(content: impl Into<String>) -> Self {
+        Self { role: Role::User, content: content.into(), tool_call_id: None, tool_calls: None }
+    }
+
+    pub fn assistant(content: impl Into<String>) -> Self {
+        Self { role: Role::Assistant, content: content.into(), tool_call_id: None, tool_calls: None }
+    }
+
+    /// An assistant turn that requests tool calls instead of answering directly.
+    pub fn assistant_tool_calls(tool_calls: Vec<ToolCall>) -> Self {
+        Self { role: Role::Assistant, content: String::new(), tool_call_id: None, tool_calls: Some(tool_calls) }
+    }
+
+    /// The result of executing a tool, sent back in reply to a tool call.
+    pub fn tool(tool_call_id: impl Into<String>, content: impl Into<String>) -> Self {
+        Self { role: Role::Tool, content: content.into(), tool_call_id: Some(tool_call_id.into()), tool_calls: None }
+    }
+}
+
+/// A tool the model may call, described as a JSON-schema function definition.
+#[derive(Debug, Clone, Serialize)]
+pub struct ToolDefinition {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub function: FunctionDefinition,
+}
+
+impl ToolDefinition {
+    pub fn function(name: impl Into<String>, description: impl Into<String>, parameters: Value) -> Self {
+        Self {
+            kind: "function".to_string(),
+            function: FunctionDefinition {
+                name: name.into(),
+                description: description.into(),
+                parameters,
+            },
+        }
+    }
+}
+
+/// The JSON-schema shape of a single callable function.
+#[derive(Debug, Clone, Serialize)]
+pub struct FunctionDefinition {
+    pub name: String,
+    pub description: String,
+    pub parameters: Value,
+}
+
+/// A tool call requested by the model, either complete (non-streaming) or a
+/// fragment that must be concatenated with other deltas sharing the same `index`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ToolCall {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub index: Option<usize>,
+    #[serde(default)]
+    pub id: Option<String>,
+    #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+    pub kind: Option<String>,
+    pub function: ToolCallFunction,
+}
+
+/// The function name and JSON-string arguments of a tool call.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ToolCallFunction {
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub arguments: String,
 }
 
 /// The main request structure sent to the GPT API.
 /// This includes all parameters that control the model's behavior.
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 pub struct GptRequest {
+    /// The model name, required by providers (like OpenAI) that don't bake the
+    /// model into the endpoint URL the way Azure deployments do.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
     pub messages: Vec<Message>,
     pub temperature: f32,
     pub max_tokens: u32,
@@ -23,7 +119,22 @@ pub struct GptRequest {
     pub presence_penalty: f32,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub stop: Option<Vec<String>>,
+    /// Tools the model may call. Omitted entirely when none are registered.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tools: Option<Vec<ToolDefinition>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_choice: Option<String>,
     pub stream: bool,
+    /// Only meaningful when `stream` is set; asks the provider to emit a final
+    /// usage frame on the SSE stream, which it otherwise omits.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stream_options: Option<StreamOptions>,
+}
+
+/// Controls what a streaming response reports beyond content deltas.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct StreamOptions {
+    pub include_usage: bool,
 }
 
 // Response-related models
@@ -33,6 +144,17 @@ pub struct GptRequest {
 pub struct GptResponse {
     pub id: Option<String>,
     pub choices: Vec<Choice>,
+    #[serde(default)]
+    pub usage: Option<Usage>,
+}
+
+/// Token accounting for a request, as reported by the API. On streaming
+/// responses this typically only appears on the final chunk.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Usage {
+    pub prompt_tokens: u32,
+    pub completion_tokens: u32,
+    pub total_tokens: u32,
 }
 
 /// A choice in the GPT response, which can contain either a complete message
@@ -48,13 +170,18 @@ pub struct Choice {
 /// A complete message in a non-streaming response.
 #[derive(Debug, Deserialize)]
 pub struct ResponseMessage {
-    pub content: String,
-    pub role: Option<String>,
+    #[serde(default)]
+    pub content: Option<String>,
+    pub role: Option<Role>,
+    #[serde(default)]
+    pub tool_calls: Option<Vec<ToolCall>>,
 }
 
 /// A partial update received during streaming.
 #[derive(Debug, Deserialize)]
 pub struct Delta {
     pub content: Option<String>,
-    pub role: Option<String>,
+    pub role: Option<Role>,
+    #[serde(default)]
+    pub tool_calls: Option<Vec<ToolCall>>,
 }
\ No newline at end of file
@@ -0,0 +1,76 @@
+// providers/azure.rs
+
+use async_trait::async_trait;
+use reqwest::{header, Client, Response};
+use serde::{Deserialize, Serialize};
+
+use crate::error::GptError;
+use crate::models::{GptRequest, GptResponse};
+
+use super::{ExtraConfig, GptProvider};
+
+/// Configuration for an Azure OpenAI chat-completions deployment.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AzureConfig {
+    /// A label identifying this client among several configured in a config file.
+    #[serde(default)]
+    pub name: Option<String>,
+    pub api_url: String,
+    pub api_key: String,
+    #[serde(default)]
+    pub extra: Option<ExtraConfig>,
+}
+
+/// Targets an Azure OpenAI deployment using the `api-key` header scheme.
+pub struct AzureProvider {
+    config: AzureConfig,
+}
+
+impl AzureProvider {
+    pub fn new(config: AzureConfig) -> Self {
+        Self { config }
+    }
+}
+
+#[async_trait]
+impl GptProvider for AzureProvider {
+    async fn complete(&self, client: &Client, request: &GptRequest) -> Result<GptResponse, GptError> {
+        let headers = self.build_headers()?;
+
+        let response = super::send(client.post(self.endpoint()).headers(headers).json(request)).await?;
+        let response = super::ensure_success(response).await?;
+
+        response.json().await.map_err(|e| GptError::ParseError(e.to_string()))
+    }
+
+    async fn complete_stream(&self, client: &Client, request: &GptRequest) -> Result<Response, GptError> {
+        let mut headers = self.build_headers()?;
+        headers.insert(
+            "Accept",
+            header::HeaderValue::from_static("text/event-stream"),
+        );
+
+        let response = super::send(client.post(self.endpoint()).headers(headers).json(request)).await?;
+        super::ensure_success(response).await
+    }
+
+    fn build_headers(&self) -> Result<header::HeaderMap, GptError> {
+        let mut headers = header::HeaderMap::new();
+
+        headers.insert(
+            "api-key",
+            header::HeaderValue::from_str(&self.config.api_key)?,
+        );
+
+        headers.insert(
+            header::CONTENT_TYPE,
+            header::HeaderValue::from_static("application/json"),
+        );
+
+        Ok(headers)
+    }
+
+    fn endpoint(&self) -> &str {
+        &self.config.api_url
+    }
+}
@@ -0,0 +1,120 @@
+// providers/mod.rs
+
+use async_trait::async_trait;
+use reqwest::{header::HeaderMap, Client, Response};
+use serde::{Deserialize, Serialize};
+
+use crate::error::GptError;
+use crate::models::{GptRequest, GptResponse};
+
+pub mod azure;
+pub mod openai;
+
+/// Networking overrides for a configured client, as loaded from the `extra`
+/// block of a YAML config entry. Mirrors the options on `GptClientBuilder`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ExtraConfig {
+    #[serde(default)]
+    pub proxy: Option<String>,
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+    #[serde(default)]
+    pub connect_timeout_secs: Option<u64>,
+}
+
+/// Abstracts over the shape of an OpenAI-compatible chat-completions backend.
+///
+/// Each backend (Azure OpenAI, OpenAI, or any other OpenAI-compatible endpoint)
+/// implements this trait to describe how it authenticates, where it lives, and
+/// how it turns a [`GptRequest`] into a response. `GptClient` holds an
+/// `Arc<dyn GptProvider>` so the REPL and the rest of the crate never need to
+/// know which backend they're talking to.
+#[async_trait]
+pub trait GptProvider: Send + Sync {
+    /// Sends a single, non-streaming completion request and returns the parsed response.
+    async fn complete(&self, client: &Client, request: &GptRequest) -> Result<GptResponse, GptError>;
+
+    /// Sends a streaming completion request and returns the raw response so the
+    /// caller can decode the `text/event-stream` body.
+    async fn complete_stream(&self, client: &Client, request: &GptRequest) -> Result<Response, GptError>;
+
+    /// Builds the headers (authentication, content type, etc.) for every request.
+    fn build_headers(&self) -> Result<HeaderMap, GptError>;
+
+    /// The full URL this provider's requests should be sent to.
+    fn endpoint(&self) -> &str;
+}
+
+/// Generates a tagged `ProviderConfig` enum that can deserialize any of the
+/// registered providers from a `type` field. It does not declare the provider
+/// modules themselves.
+///
+/// Once a backend's module exists (a config type with an `extra` field and a
+/// client type with a `new(config)` constructor), registering it takes a new
+/// `(Variant, "name", ConfigType, ClientType)` entry in the [`register_provider!`]
+/// invocation below.
+#[macro_export]
+macro_rules! register_provider {
+    ($(($variant:ident, $name:literal, $config:ty, $client:ty)),* $(,)?) => {
+        /// Tagged configuration for every registered provider.
+        ///
+        /// Deserializes from a `type` discriminator, e.g. `{"type": "azure", ...}`.
+        #[derive(Debug, Clone, Serialize, Deserialize)]
+        #[serde(tag = "type")]
+        pub enum ProviderConfig {
+            $(
+                #[serde(rename = $name)]
+                $variant($config),
+            )*
+        }
+
+        impl ProviderConfig {
+            /// Builds the concrete [`GptProvider`] described by this configuration.
+            pub fn build(self) -> Box<dyn GptProvider> {
+                match self {
+                    $(ProviderConfig::$variant(config) => Box::new(<$client>::new(config)),)*
+                }
+            }
+
+            /// The networking overrides configured for this client, if any.
+            pub fn extra(&self) -> Option<&ExtraConfig> {
+                match self {
+                    $(ProviderConfig::$variant(config) => config.extra.as_ref(),)*
+                }
+            }
+        }
+    };
+}
+
+register_provider!(
+    (Azure, "azure", azure::AzureConfig, azure::AzureProvider),
+    (OpenAi, "openai", openai::OpenAiConfig, openai::OpenAiProvider),
+);
+
+/// Sends a request, mapping a timed-out send into `GptError::Timeout` rather
+/// than the generic `RequestError` so callers can distinguish the two.
+pub(crate) async fn send(request: reqwest::RequestBuilder) -> Result<Response, GptError> {
+    request.send().await.map_err(|e| {
+        if e.is_timeout() {
+            GptError::Timeout
+        } else {
+            GptError::RequestError(e)
+        }
+    })
+}
+
+/// Turns a non-success HTTP response into a `GptError::ApiError` carrying the
+/// provider's error body, so every backend reports failures the same way.
+/// Passes successful responses through unchanged.
+pub(crate) async fn ensure_success(response: Response) -> Result<Response, GptError> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+
+    let message = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+    Err(GptError::ApiError {
+        status_code: status.as_u16(),
+        message,
+    })
+}
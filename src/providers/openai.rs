@@ -0,0 +1,100 @@
+// providers/openai.rs
+
+use async_trait::async_trait;
+use reqwest::{header, Client, Response};
+use serde::{Deserialize, Serialize};
+
+use crate::error::GptError;
+use crate::models::{GptRequest, GptResponse};
+
+use super::{ExtraConfig, GptProvider};
+
+/// Configuration for OpenAI's own API, or any OpenAI-compatible endpoint that
+/// authenticates with a bearer token and expects a `model` field in the body.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpenAiConfig {
+    /// A label identifying this client among several configured in a config file.
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default = "default_api_url")]
+    pub api_url: String,
+    pub api_key: String,
+    pub model: String,
+    #[serde(default)]
+    pub organization_id: Option<String>,
+    #[serde(default)]
+    pub extra: Option<ExtraConfig>,
+}
+
+fn default_api_url() -> String {
+    "https://api.openai.com/v1/chat/completions".to_string()
+}
+
+/// Targets OpenAI's chat-completions API using the `Bearer` auth scheme.
+pub struct OpenAiProvider {
+    config: OpenAiConfig,
+}
+
+impl OpenAiProvider {
+    pub fn new(config: OpenAiConfig) -> Self {
+        Self { config }
+    }
+
+    fn with_model(&self, request: &GptRequest) -> GptRequest {
+        let mut request = request.clone();
+        request.model = Some(self.config.model.clone());
+        request
+    }
+}
+
+#[async_trait]
+impl GptProvider for OpenAiProvider {
+    async fn complete(&self, client: &Client, request: &GptRequest) -> Result<GptResponse, GptError> {
+        let headers = self.build_headers()?;
+        let request = self.with_model(request);
+
+        let response = super::send(client.post(self.endpoint()).headers(headers).json(&request)).await?;
+        let response = super::ensure_success(response).await?;
+
+        response.json().await.map_err(|e| GptError::ParseError(e.to_string()))
+    }
+
+    async fn complete_stream(&self, client: &Client, request: &GptRequest) -> Result<Response, GptError> {
+        let mut headers = self.build_headers()?;
+        headers.insert(
+            "Accept",
+            header::HeaderValue::from_static("text/event-stream"),
+        );
+        let request = self.with_model(request);
+
+        let response = super::send(client.post(self.endpoint()).headers(headers).json(&request)).await?;
+        super::ensure_success(response).await
+    }
+
+    fn build_headers(&self) -> Result<header::HeaderMap, GptError> {
+        let mut headers = header::HeaderMap::new();
+
+        headers.insert(
+            header::AUTHORIZATION,
+            header::HeaderValue::from_str(&format!("Bearer {}", self.config.api_key))?,
+        );
+
+        if let Some(organization_id) = &self.config.organization_id {
+            headers.insert(
+                "OpenAI-Organization",
+                header::HeaderValue::from_str(organization_id)?,
+            );
+        }
+
+        headers.insert(
+            header::CONTENT_TYPE,
+            header::HeaderValue::from_static("application/json"),
+        );
+
+        Ok(headers)
+    }
+
+    fn endpoint(&self) -> &str {
+        &self.config.api_url
+    }
+}
@@ -0,0 +1,168 @@
+// retry.rs
+
+use std::time::Duration;
+
+use rand::Rng;
+
+use crate::error::GptError;
+
+/// Governs how `GptClient` retries transient HTTP failures.
+#[derive(Debug, Clone)]
+pub struct RetryConfig {
+    pub max_retries: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            base_delay: Duration::from_millis(200),
+            max_delay: Duration::from_secs(10),
+        }
+    }
+}
+
+impl RetryConfig {
+    /// Runs `op`, retrying transient failures (connection errors, timeouts,
+    /// and HTTP 429/5xx) with exponential backoff plus jitter.
+    pub async fn run<T, F, Fut>(&self, mut op: F) -> Result<T, GptError>
+    where
+        F: FnMut() -> Fut,
+        Fut: std::future::Future<Output = Result<T, GptError>>,
+    {
+        let mut attempt = 0;
+
+        loop {
+            match op().await {
+                Ok(value) => return Ok(value),
+                Err(e) if attempt < self.max_retries && is_transient(&e) => {
+                    let delay = self.backoff_delay(attempt);
+                    tracing::warn!(
+                        "Transient error ({}), retrying in {:?} (attempt {}/{})",
+                        e,
+                        delay,
+                        attempt + 1,
+                        self.max_retries
+                    );
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    fn backoff_delay(&self, attempt: u32) -> Duration {
+        let exponential = self.base_delay.saturating_mul(1 << attempt.min(16));
+        let capped = exponential.min(self.max_delay);
+        let jitter = rand::thread_rng().gen_range(0..=capped.as_millis() as u64 / 4 + 1);
+        capped + Duration::from_millis(jitter)
+    }
+}
+
+fn is_transient(error: &GptError) -> bool {
+    match error {
+        GptError::RequestError(e) => e.is_connect() || e.is_timeout(),
+        GptError::Timeout => true,
+        GptError::ApiError { status_code, .. } => *status_code == 429 || *status_code >= 500,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn is_transient_retries_timeouts_and_server_errors() {
+        assert!(is_transient(&GptError::Timeout));
+        assert!(is_transient(&GptError::ApiError { status_code: 429, message: String::new() }));
+        assert!(is_transient(&GptError::ApiError { status_code: 503, message: String::new() }));
+    }
+
+    #[test]
+    fn is_transient_does_not_retry_client_errors() {
+        assert!(!is_transient(&GptError::ApiError { status_code: 400, message: String::new() }));
+        assert!(!is_transient(&GptError::ConfigError("bad config".to_string())));
+        assert!(!is_transient(&GptError::UnknownTool("missing".to_string())));
+    }
+
+    #[test]
+    fn backoff_delay_grows_with_attempt_and_caps_at_max_delay() {
+        let retry = RetryConfig::default();
+        let jitter_ceiling = |capped: Duration| capped + Duration::from_millis(capped.as_millis() as u64 / 4 + 1);
+
+        let first = retry.backoff_delay(0);
+        assert!(first >= retry.base_delay && first <= jitter_ceiling(retry.base_delay));
+
+        let second = retry.backoff_delay(1);
+        assert!(second >= retry.base_delay * 2 && second <= jitter_ceiling(retry.base_delay * 2));
+
+        let saturated = retry.backoff_delay(20);
+        assert!(saturated >= retry.max_delay && saturated <= jitter_ceiling(retry.max_delay));
+    }
+
+    #[tokio::test]
+    async fn run_retries_transient_errors_until_success() {
+        let retry = RetryConfig {
+            max_retries: 3,
+            base_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(5),
+        };
+
+        let attempts = std::sync::atomic::AtomicU32::new(0);
+        let result = retry
+            .run(|| {
+                let attempt = attempts.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                async move {
+                    if attempt < 2 {
+                        Err(GptError::Timeout)
+                    } else {
+                        Ok::<_, GptError>("done")
+                    }
+                }
+            })
+            .await;
+
+        assert_eq!(result.unwrap(), "done");
+        assert_eq!(attempts.load(std::sync::atomic::Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn run_gives_up_after_max_retries() {
+        let retry = RetryConfig {
+            max_retries: 2,
+            base_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(5),
+        };
+
+        let attempts = std::sync::atomic::AtomicU32::new(0);
+        let result: Result<(), GptError> = retry
+            .run(|| {
+                attempts.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                async { Err(GptError::Timeout) }
+            })
+            .await;
+
+        assert!(matches!(result, Err(GptError::Timeout)));
+        assert_eq!(attempts.load(std::sync::atomic::Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn run_does_not_retry_non_transient_errors() {
+        let retry = RetryConfig::default();
+
+        let attempts = std::sync::atomic::AtomicU32::new(0);
+        let result: Result<(), GptError> = retry
+            .run(|| {
+                attempts.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                async { Err(GptError::UnknownTool("missing".to_string())) }
+            })
+            .await;
+
+        assert!(matches!(result, Err(GptError::UnknownTool(_))));
+        assert_eq!(attempts.load(std::sync::atomic::Ordering::SeqCst), 1);
+    }
+}
@@ -0,0 +1,123 @@
+// stream.rs
+
+use eventsource_stream::Eventsource;
+use futures::StreamExt;
+use reqwest::Response;
+use tokio::sync::mpsc;
+
+use crate::error::GptError;
+use crate::models::{GptResponse, ToolCall, Usage};
+
+/// A single event surfaced while consuming a streaming completion.
+///
+/// Replaces the old raw `String` stream item so callers can react to
+/// completion and token usage instead of only ever seeing text deltas.
+#[derive(Debug, Clone)]
+pub enum StreamEvent {
+    /// A fragment of the assistant's reply.
+    Content(String),
+    /// The reason the model stopped generating (e.g. `"stop"`, `"tool_calls"`).
+    FinishReason(String),
+    /// Token usage for the request, when the provider reports it.
+    Usage(Usage),
+}
+
+/// What a single streamed round produced, once its SSE body is fully decoded:
+/// the assistant's accumulated reply text, any tool calls it requested (their
+/// argument fragments merged by `index`), and the reason generation stopped.
+#[derive(Debug, Default)]
+pub(crate) struct StreamRoundOutcome {
+    pub content: String,
+    pub tool_calls: Vec<ToolCall>,
+    pub finish_reason: Option<String>,
+}
+
+/// Decodes a provider's `text/event-stream` response body for a single round,
+/// forwarding content deltas, the finish reason, and usage to `tx` as they
+/// arrive, and merging streamed tool-call argument fragments by `index` along
+/// the way. The caller drives any resulting tool-calling loop.
+///
+/// Uses an `eventsource_stream` decoder rather than hand-rolled `"\n\n"`
+/// splitting, so multi-line `data:` fields, chunk boundaries mid-event, and
+/// comment/heartbeat lines are all handled correctly.
+pub(crate) async fn stream_round(
+    response: Response,
+    tx: &mpsc::Sender<Result<StreamEvent, GptError>>,
+) -> StreamRoundOutcome {
+    let mut events = response.bytes_stream().eventsource();
+    let mut outcome = StreamRoundOutcome::default();
+
+    while let Some(event_result) = events.next().await {
+        let event = match event_result {
+            Ok(event) => event,
+            Err(e) => {
+                let _ = tx.send(Err(GptError::ParseError(e.to_string()))).await;
+                break;
+            }
+        };
+
+        if event.data == "[DONE]" {
+            break;
+        }
+
+        match serde_json::from_str::<GptResponse>(&event.data) {
+            Ok(response) => {
+                if let Some(choice) = response.choices.first() {
+                    if let Some(delta) = &choice.delta {
+                        if let Some(content) = &delta.content {
+                            if !content.is_empty() {
+                                outcome.content.push_str(content);
+                                let _ = tx.send(Ok(StreamEvent::Content(content.clone()))).await;
+                            }
+                        }
+
+                        if let Some(tool_call_deltas) = &delta.tool_calls {
+                            merge_tool_call_deltas(&mut outcome.tool_calls, tool_call_deltas);
+                        }
+                    }
+
+                    if let Some(finish_reason) = &choice.finish_reason {
+                        outcome.finish_reason = Some(finish_reason.clone());
+                        let _ = tx.send(Ok(StreamEvent::FinishReason(finish_reason.clone()))).await;
+                    }
+                }
+
+                if let Some(usage) = response.usage {
+                    let _ = tx.send(Ok(StreamEvent::Usage(usage))).await;
+                }
+            }
+            Err(e) => {
+                tracing::error!("Failed to parse stream event: {}", e);
+                let _ = tx.send(Err(GptError::ParseError(e.to_string()))).await;
+            }
+        }
+    }
+
+    outcome
+}
+
+/// Merges streamed tool-call argument fragments into `accumulated` by their
+/// `index`, the only identifier stable across a call's fragments (`id` and the
+/// function `name` are typically only present on the first fragment, with
+/// `arguments` dribbled out a few characters at a time after that).
+fn merge_tool_call_deltas(accumulated: &mut Vec<ToolCall>, deltas: &[ToolCall]) {
+    for delta in deltas {
+        let index = delta.index.unwrap_or(0);
+
+        match accumulated.iter_mut().find(|call| call.index.unwrap_or(0) == index) {
+            Some(existing) => {
+                if delta.id.is_some() {
+                    existing.id = delta.id.clone();
+                }
+                if delta.kind.is_some() {
+                    existing.kind = delta.kind.clone();
+                }
+                if delta.function.name.is_some() {
+                    existing.function.name = delta.function.name.clone();
+                }
+                existing.function.arguments.push_str(&delta.function.arguments);
+            }
+            None => accumulated.push(delta.clone()),
+        }
+    }
+}
@@ -0,0 +1,66 @@
+// tokenizer.rs
+
+use crate::models::Message;
+
+/// Rough token-count estimate for a piece of text.
+///
+/// Approximates the commonly used "~4 characters per token" heuristic rather
+/// than pulling in a full BPE tokenizer, since this is only used for
+/// client-side context budgeting, not billing.
+pub fn count_tokens(text: &str) -> usize {
+    (text.chars().count() as f64 / 4.0).ceil() as usize
+}
+
+/// Per-message overhead (role, separators, etc.) added on top of content
+/// tokens, mirroring the rough accounting OpenAI documents for its chat format.
+const MESSAGE_OVERHEAD_TOKENS: usize = 4;
+
+/// Estimates the total token count of a full message history.
+///
+/// `assistant` turns that request tool calls carry little or no `content`,
+/// so the call's function name and JSON arguments are counted too — otherwise
+/// a history full of large tool-call arguments looks artificially small.
+pub fn count_message_tokens(messages: &[Message]) -> usize {
+    messages.iter().map(count_single_message_tokens).sum()
+}
+
+fn count_single_message_tokens(message: &Message) -> usize {
+    let mut tokens = count_tokens(&message.content) + MESSAGE_OVERHEAD_TOKENS;
+
+    if let Some(tool_calls) = &message.tool_calls {
+        let serialized = serde_json::to_string(tool_calls).unwrap_or_default();
+        tokens += count_tokens(&serialized);
+    }
+
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{ToolCall, ToolCallFunction};
+
+    #[test]
+    fn count_tokens_rounds_up_to_the_nearest_token() {
+        assert_eq!(count_tokens(""), 0);
+        assert_eq!(count_tokens("abcd"), 1);
+        assert_eq!(count_tokens("abcde"), 2);
+    }
+
+    #[test]
+    fn count_message_tokens_includes_tool_call_arguments() {
+        let plain = vec![Message::user("hi")];
+
+        let tool_call = ToolCall {
+            id: Some("call-1".to_string()),
+            function: ToolCallFunction {
+                name: Some("lookup".to_string()),
+                arguments: "{\"query\": \"a very long search string used to pad this out\"}".to_string(),
+            },
+            ..Default::default()
+        };
+        let with_tool_call = vec![Message::assistant_tool_calls(vec![tool_call])];
+
+        assert!(count_message_tokens(&with_tool_call) > count_message_tokens(&plain));
+    }
+}
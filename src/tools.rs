@@ -0,0 +1,49 @@
+// tools.rs
+
+use async_trait::async_trait;
+use serde_json::Value;
+
+use crate::error::GptError;
+use crate::models::ToolDefinition;
+
+/// A function the model may choose to invoke mid-conversation.
+#[async_trait]
+pub trait Tool: Send + Sync {
+    /// The tool's name, as the model will refer to it in a tool call.
+    fn name(&self) -> &str;
+
+    /// The JSON-schema-shaped definition advertised to the model.
+    fn schema(&self) -> ToolDefinition;
+
+    /// Executes the tool with the model-supplied arguments, returning the
+    /// result to be sent back as a `tool` message.
+    async fn call(&self, args: Value) -> Result<String, GptError>;
+}
+
+/// Looks up registered tools by name when the model requests a call.
+#[derive(Default)]
+pub struct ToolRegistry {
+    tools: Vec<Box<dyn Tool>>,
+}
+
+impl ToolRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register(&mut self, tool: Box<dyn Tool>) {
+        self.tools.push(tool);
+    }
+
+    pub fn get(&self, name: &str) -> Option<&dyn Tool> {
+        self.tools.iter().find(|tool| tool.name() == name).map(|tool| tool.as_ref())
+    }
+
+    pub fn schemas(&self) -> Vec<ToolDefinition> {
+        self.tools.iter().map(|tool| tool.schema()).collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tools.is_empty()
+    }
+}